@@ -50,6 +50,466 @@ pub mod minspan {
     
         best_span
     }
+
+    // `Pattern`/`Searcher`-style generalization of `span`: each query position
+    // is a predicate (a character class, a case-insensitive slot, a wildcard,
+    // ...) instead of a literal value, but the minimal-window DP is unchanged.
+    pub fn span_by<A, F>(query: &mut [F], history: &[A]) -> Option<(usize, usize)>
+    where
+        F: FnMut(&A, usize) -> bool,
+    {
+        if query.is_empty() {
+            return Some((0, 0));
+        }
+
+        let mut start_indices = vec![None; query.len()]; // Track the start indices for each query element.
+        let mut best_span: Option<(usize, usize)> = None;
+
+        for (bodyindex, bodychr) in history.iter().enumerate() {
+            // Check for each element in the query.
+            for keyindex in (0..query.len()).rev() {
+                if query[keyindex](bodychr, bodyindex) {
+                    // We found a match for query[keyindex] at bodyindex.
+                    start_indices[keyindex] = if keyindex == 0 {
+                        // If it's the first character in the query, it starts a potential match.
+                        Some(bodyindex)
+                    } else {
+                        // Otherwise, we extend the match from the previous element.
+                        start_indices[keyindex - 1]
+                    };
+
+                    // If we have a match for the entire query, update the best span.
+                    if keyindex == query.len() - 1 {
+                        if let Some(start) = start_indices[query.len() - 1] {
+                            let end = bodyindex;
+                            let span = (start, end);
+
+                            best_span = match best_span {
+                                None => Some(span),
+                                Some((curr_start, curr_end)) => {
+                                    if end - start < curr_end - curr_start {
+                                        Some(span)
+                                    } else {
+                                        Some((curr_start, curr_end))
+                                    }
+                                }
+                            };
+                        }
+                    }
+                }
+            }
+        }
+
+        best_span
+    }
+
+    // Bonuses/penalties for `span_scored`, loosely modeled on nucleo's fuzzy
+    // scoring: reward runs of consecutive matches and matches that land on a
+    // word boundary, and penalize the gaps between matched positions.
+    const CONSECUTIVE_BONUS: i32 = 8;
+    const BOUNDARY_BONUS: i32 = 6;
+    const GAP_PENALTY: i32 = 1;
+
+    fn is_word_boundary(history: &[char], index: usize) -> bool {
+        if index == 0 {
+            return true;
+        }
+        let prev = history[index - 1];
+        let curr = history[index];
+        !prev.is_alphanumeric() || (prev.is_lowercase() && curr.is_uppercase())
+    }
+
+    #[derive(Clone, Copy)]
+    struct ScoreCell {
+        score: i32,
+        start: usize,
+        prev: Option<usize>,
+    }
+
+    // Prefer the higher score; break ties by the shorter span so far, then by
+    // the leftmost start.
+    fn better(a: &ScoreCell, a_end: usize, b: &ScoreCell, b_end: usize) -> bool {
+        if a.score != b.score {
+            return a.score > b.score;
+        }
+        let a_len = a_end - a.start;
+        let b_len = b_end - b.start;
+        if a_len != b_len {
+            return a_len < b_len;
+        }
+        a.start < b.start
+    }
+
+    // Scores the match the way a fuzzy picker would: a small DP over the
+    // window rewards consecutive matches and word boundaries, and penalizes
+    // gaps between matched positions. Returns the window, the score, and the
+    // concrete index in `history` matched by each query element, preferring
+    // the highest score (ties broken by shortest span, then leftmost).
+    pub fn span_scored(query: &[char], history: &[char]) -> Option<(usize, usize, i32, Vec<usize>)> {
+        if query.is_empty() {
+            return Some((0, 0, 0, Vec::new()));
+        }
+        let n = query.len();
+        let m = history.len();
+        if m < n {
+            return None;
+        }
+
+        // dp[k][j] holds the best way to match query[..=k] ending with
+        // query[k] matched at history[j].
+        let mut dp: Vec<Vec<Option<ScoreCell>>> = vec![vec![None; m]; n];
+
+        for j in 0..m {
+            if history[j] == query[0] {
+                let score = if is_word_boundary(history, j) { BOUNDARY_BONUS } else { 0 };
+                dp[0][j] = Some(ScoreCell { score, start: j, prev: None });
+            }
+        }
+
+        for k in 1..n {
+            // `running_gap_best` tracks the best predecessor `i` (by score
+            // adjusted for the gap penalty it will incur) seen so far, so each
+            // `j` only has to compare against its immediate predecessor (the
+            // consecutive-match case) plus this one running value, instead of
+            // rescanning every earlier `i`.
+            let mut running_gap_best: Option<ScoreCell> = None;
+
+            for j in 0..m {
+                // The gap penalty for predecessor `i` is
+                // `-(j - i - 1) * GAP_PENALTY`, i.e. `GAP_PENALTY * (i + 1) -
+                // GAP_PENALTY * j`. The `GAP_PENALTY * j` term is common to
+                // every `i` at this `j`, so ranking by the adjusted value
+                // `score + GAP_PENALTY * (i + 1)` ranks candidates the same
+                // way the final score would, for any `j`. Fold in `i = j - 2`
+                // (the newest index eligible for the gap case) before using
+                // `running_gap_best` below.
+                if j >= 2 {
+                    if let Some(prev_cell) = dp[k - 1][j - 2] {
+                        let candidate = ScoreCell {
+                            score: prev_cell.score + GAP_PENALTY * (j - 2 + 1) as i32,
+                            start: prev_cell.start,
+                            prev: Some(j - 2),
+                        };
+                        running_gap_best = match running_gap_best {
+                            None => Some(candidate),
+                            Some(curr) if candidate.score > curr.score => Some(candidate),
+                            // On equal adjusted scores, the candidate whose match
+                            // started later gives the shorter final span, so it
+                            // wins the tie.
+                            Some(curr) if candidate.score == curr.score && candidate.start > curr.start => {
+                                Some(candidate)
+                            }
+                            Some(curr) => Some(curr),
+                        };
+                    }
+                }
+
+                if history[j] != query[k] {
+                    continue;
+                }
+                let boundary = if is_word_boundary(history, j) { BOUNDARY_BONUS } else { 0 };
+                let mut best: Option<ScoreCell> = None;
+
+                if j >= 1 {
+                    if let Some(prev_cell) = dp[k - 1][j - 1] {
+                        best = Some(ScoreCell {
+                            score: prev_cell.score + CONSECUTIVE_BONUS + boundary,
+                            start: prev_cell.start,
+                            prev: Some(j - 1),
+                        });
+                    }
+                }
+
+                if let Some(gap_best) = running_gap_best {
+                    let candidate = ScoreCell {
+                        score: gap_best.score - GAP_PENALTY * j as i32 + boundary,
+                        start: gap_best.start,
+                        prev: gap_best.prev,
+                    };
+                    best = match best {
+                        None => Some(candidate),
+                        Some(curr) => {
+                            if better(&candidate, j, &curr, j) {
+                                Some(candidate)
+                            } else {
+                                Some(curr)
+                            }
+                        }
+                    };
+                }
+
+                dp[k][j] = best;
+            }
+        }
+
+        let mut best_end: Option<usize> = None;
+        for j in 0..m {
+            let Some(cell) = dp[n - 1][j] else { continue };
+            best_end = match best_end {
+                None => Some(j),
+                Some(curr_j) => {
+                    let curr = dp[n - 1][curr_j].unwrap();
+                    if better(&cell, j, &curr, curr_j) {
+                        Some(j)
+                    } else {
+                        Some(curr_j)
+                    }
+                }
+            };
+        }
+
+        let end = best_end?;
+        let mut indices = vec![0; n];
+        let mut cursor = end;
+        for k in (0..n).rev() {
+            indices[k] = cursor;
+            if let Some(prev) = dp[k][cursor].unwrap().prev {
+                cursor = prev;
+            }
+        }
+
+        let score = dp[n - 1][end].unwrap().score;
+        let start = indices[0];
+        Some((start, end, score, indices))
+    }
+
+    // Scans `history` from the end toward the start, preferring (among
+    // equally short windows) the one closest to the end rather than the
+    // start -- useful for things like command histories, where the most
+    // recent matching span is the interesting one. Mirrors `span`'s
+    // `start_indices` DP, tracking the end index for each query element
+    // instead of the start.
+    pub fn span_rev<A>(query: &[A], history: &[A]) -> Option<(usize, usize)>
+    where
+        A: PartialEq,
+    {
+        if query.is_empty() {
+            return Some((history.len(), history.len()));
+        }
+
+        let mut end_indices = vec![None; query.len()]; // Track the end indices for each query element.
+        let mut best_span: Option<(usize, usize)> = None;
+
+        for (bodyindex, bodychr) in history.iter().enumerate().rev() {
+            // Check for each element in the query, from first to last.
+            for keyindex in 0..query.len() {
+                if &query[keyindex] == bodychr {
+                    // We found a match for query[keyindex] at bodyindex.
+                    end_indices[keyindex] = if keyindex == query.len() - 1 {
+                        // If it's the last character in the query, it starts a potential match.
+                        Some(bodyindex)
+                    } else {
+                        // Otherwise, we extend the match from the next element.
+                        end_indices[keyindex + 1]
+                    };
+
+                    // If we have a match for the entire query, update the best span.
+                    if keyindex == 0 {
+                        if let Some(end) = end_indices[0] {
+                            let start = bodyindex;
+                            let span = (start, end);
+
+                            best_span = match best_span {
+                                None => Some(span),
+                                Some((curr_start, curr_end)) => {
+                                    if end - start < curr_end - curr_start {
+                                        Some(span)
+                                    } else {
+                                        Some((curr_start, curr_end))
+                                    }
+                                }
+                            };
+                        }
+                    }
+                }
+            }
+        }
+
+        best_span
+    }
+
+    // Finds the maximal suffix of `arr` under lexicographic order (or, with
+    // `reversed`, under the reverse order), returning the position at which
+    // that suffix starts along with the period of the repeating factor that
+    // produced it. This is the standard building block for the critical
+    // factorization used by the Two-Way string matching algorithm.
+    fn maximal_suffix<A: Ord>(arr: &[A], reversed: bool) -> (usize, usize) {
+        use std::cmp::Ordering;
+
+        let mut left = 0; // Start of the current candidate suffix.
+        let mut right = 1; // Start of the suffix being compared against.
+        let mut offset = 0; // How far into both suffixes we've already matched.
+        let mut period = 1; // Period of the candidate suffix.
+
+        while right + offset < arr.len() {
+            let a = &arr[right + offset];
+            let b = &arr[left + offset];
+            let ordering = if reversed { b.cmp(a) } else { a.cmp(b) };
+            match ordering {
+                Ordering::Less => {
+                    right += offset + 1;
+                    offset = 0;
+                    period = right - left;
+                }
+                Ordering::Equal => {
+                    if offset + 1 == period {
+                        right += offset + 1;
+                        offset = 0;
+                    } else {
+                        offset += 1;
+                    }
+                }
+                Ordering::Greater => {
+                    left = right;
+                    right += 1;
+                    offset = 0;
+                    period = 1;
+                }
+            }
+        }
+
+        (left, period)
+    }
+
+    // Opt-in contiguous-substring mode: unlike `span`, the query must appear
+    // as a contiguous run in `history`. Implemented with the Two-Way string
+    // matching algorithm (the same approach `str`'s `Searcher` uses), which
+    // gives O(n) worst-case search with O(1) extra space and avoids the
+    // O(n*m) blowup `span`'s subsequence DP would hit on adversarial inputs
+    // like a million 'a's followed by a 'b'. Returns
+    // `(start, start + query.len() - 1)` for the first occurrence.
+    pub fn span_contiguous<A>(query: &[A], history: &[A]) -> Option<(usize, usize)>
+    where
+        A: Ord,
+    {
+        if query.is_empty() {
+            return Some((0, 0));
+        }
+        if query.len() > history.len() {
+            return None;
+        }
+
+        // Critical factorization: split `query` into `query[..crit_pos]` and
+        // `query[crit_pos..]` at the position that maximizes the suffix under
+        // both normal and reversed ordering.
+        let (crit_pos_normal, period_normal) = maximal_suffix(query, false);
+        let (crit_pos_reversed, period_reversed) = maximal_suffix(query, true);
+        let (crit_pos, period) = if crit_pos_normal > crit_pos_reversed {
+            (crit_pos_normal, period_normal)
+        } else {
+            (crit_pos_reversed, period_reversed)
+        };
+
+        // If the left part repeats with the candidate period, we can safely
+        // remember how much of it is already known to match and skip ahead by
+        // `period` instead of restarting from scratch. Otherwise fall back to
+        // a shift of `max(crit_pos, query.len() - crit_pos) + 1` and keep no
+        // memory between alignments.
+        let small_period = query[..crit_pos] == query[period..period + crit_pos];
+        let period = if small_period {
+            period
+        } else {
+            std::cmp::max(crit_pos, query.len() - crit_pos) + 1
+        };
+
+        let mut pos = 0;
+        let mut memory = 0;
+
+        while pos + query.len() <= history.len() {
+            // Scan the right part first, left-to-right from `crit_pos`.
+            let mut i = std::cmp::max(crit_pos, memory);
+            while i < query.len() && query[i] == history[pos + i] {
+                i += 1;
+            }
+            if i < query.len() {
+                // Mismatch in the right part: shift past it entirely.
+                pos += i - crit_pos + 1;
+                memory = 0;
+                continue;
+            }
+
+            // Right part matched in full; verify the left part.
+            let mut j = crit_pos;
+            while j > memory && query[j - 1] == history[pos + j - 1] {
+                j -= 1;
+            }
+            if j <= memory {
+                return Some((pos, pos + query.len() - 1));
+            }
+            pos += period;
+            memory = if small_period { query.len() - period } else { 0 };
+        }
+
+        None
+    }
+
+    // Folds a run of `chars` down to the form `span_folded` matches against:
+    // ASCII letters are lowercased, and runs of codepoints that
+    // Unicode-normalize (NFC) down to a single composed character — e.g. a
+    // base letter followed by a combining accent — are collapsed into that
+    // one character. Returns the folded characters alongside, for each one,
+    // the index in the original `chars` where it started, so callers can map
+    // folded positions back to the original, un-normalized sequence.
+    fn fold_chars(chars: &[char]) -> (Vec<char>, Vec<usize>) {
+        use unicode_normalization::UnicodeNormalization;
+
+        let mut folded = Vec::with_capacity(chars.len());
+        let mut origins = Vec::with_capacity(chars.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            // Greedily look for the longest run (a base character plus a
+            // handful of combining marks) that normalizes to one character.
+            let max_len = chars.len() - i;
+            let mut run_len = 1;
+            for len in (1..=max_len.min(4)).rev() {
+                let run: String = chars[i..i + len].iter().collect();
+                if run.nfc().count() == 1 {
+                    run_len = len;
+                    break;
+                }
+            }
+
+            let run: String = chars[i..i + run_len].iter().collect();
+            let composed: String = run.nfc().collect();
+            let mut folded_char = composed.chars().next().unwrap();
+            if folded_char.is_ascii() {
+                folded_char = folded_char.to_ascii_lowercase();
+            }
+
+            folded.push(folded_char);
+            origins.push(i);
+            i += run_len;
+        }
+
+        (folded, origins)
+    }
+
+    // ASCII case-insensitive, normalization-aware matching: query elements
+    // are compared after ASCII case-folding and Unicode canonical
+    // normalization (NFC), so a precomposed character on one side matches an
+    // equivalent decomposed sequence on the other. Accents are not stripped:
+    // "e" does not match "é", and non-ASCII letters keep their case.
+    // Normalization can change how many elements a run of `history` takes
+    // up, so the returned indices
+    // are mapped back to positions in the original, un-normalized `history`.
+    pub fn span_folded(query: &[char], history: &[char]) -> Option<(usize, usize)> {
+        if query.is_empty() {
+            return Some((0, 0));
+        }
+
+        let (folded_query, _) = fold_chars(query);
+        let (folded_history, origins) = fold_chars(history);
+
+        let (fold_start, fold_end) = span(&folded_query, &folded_history)?;
+        let start = origins[fold_start];
+        let end = if fold_end + 1 < origins.len() {
+            origins[fold_end + 1] - 1
+        } else {
+            history.len() - 1
+        };
+        Some((start, end))
+    }
 }
 
 #[cfg(test)]
@@ -119,8 +579,161 @@ mod tests {
         assert_eq!(wrapper("ab", &large_haystack), Some(2)); // Match at the end
 
         // Test with Unicode characters
-        assert_eq!(wrapper("ã“ã‚“ã«ã¡ã¯", "ã“ã‚Œã¯ã“ã‚“ã«ã¡ã¯ä¸–ç•Œ"), Some(5)); // Matches the Japanese substring
-        assert_eq!(wrapper("ä½ å¥½", "ä½ å¥½å—"), Some(2)); // Chinese characters match
-        assert_eq!(wrapper("ğŸ˜Š", "abcğŸ˜Šdef"), Some(1)); // Matches emoji
+        assert_eq!(wrapper("こんにちは", "これはこんにちは世界"), Some(5)); // Matches the Japanese substring
+        assert_eq!(wrapper("你好", "你好吗"), Some(2)); // Chinese characters match
+        assert_eq!(wrapper("😊", "abc😊def"), Some(1)); // Matches emoji
+    }
+
+    type Predicate = Box<dyn FnMut(&char, usize) -> bool>;
+
+    #[test]
+    fn test_span_by_predicates() {
+        // Each predicate receives the history element and its index, so we can
+        // express character classes, case-insensitive slots, and wildcards.
+        let is_vowel = |c: &char, _: usize| "aeiouAEIOU".contains(*c);
+        let mut query: Vec<Predicate> = vec![Box::new(is_vowel)];
+        let history: Vec<char> = "xyzaqr".chars().collect();
+        assert_eq!(minspan::span_by(&mut query, &history), Some((3, 3)));
+
+        // Case-insensitive literal slots.
+        let mut query: Vec<Predicate> = vec![
+            Box::new(|c: &char, _: usize| c.eq_ignore_ascii_case(&'a')),
+            Box::new(|c: &char, _: usize| c.eq_ignore_ascii_case(&'b')),
+        ];
+        let history: Vec<char> = "xxABxx".chars().collect();
+        assert_eq!(minspan::span_by(&mut query, &history), Some((2, 3)));
+
+        // A wildcard slot matches anything, so it should find the shortest
+        // window around whatever sits between the two literal matches.
+        let mut query: Vec<Predicate> = vec![
+            Box::new(|c: &char, _: usize| *c == 'a'),
+            Box::new(|_: &char, _: usize| true),
+            Box::new(|c: &char, _: usize| *c == 'c'),
+        ];
+        let history: Vec<char> = "zaxcz".chars().collect();
+        assert_eq!(minspan::span_by(&mut query, &history), Some((1, 3)));
+
+        // No match at all.
+        let mut query: Vec<Predicate> = vec![Box::new(|c: &char, _: usize| *c == 'q')];
+        let history: Vec<char> = "abc".chars().collect();
+        assert_eq!(minspan::span_by(&mut query, &history), None);
+
+        // Empty query always matches a trivial zero-width span.
+        let mut query: Vec<Predicate> = vec![];
+        let history: Vec<char> = "abc".chars().collect();
+        assert_eq!(minspan::span_by(&mut query, &history), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_span_scored() {
+        let wrapper = |needle: &str, haystack: &str| {
+            minspan::span_scored(
+                &needle.chars().collect::<Vec<char>>(),
+                &haystack.chars().collect::<Vec<char>>(),
+            )
+        };
+
+        // A fully consecutive match scores higher than a scattered one, even
+        // though both are valid subsequences.
+        let (start, end, score, indices) = wrapper("ab", "xxabxx").unwrap();
+        assert_eq!((start, end), (2, 3));
+        assert_eq!(indices, vec![2, 3]);
+        assert!(score > 0);
+
+        let (_, _, scattered_score, _) = wrapper("ab", "axxxxxb").unwrap();
+        assert!(score > scattered_score);
+
+        // A match starting at a word boundary scores higher than the same
+        // consecutive match one position later, where it isn't.
+        let (_, _, boundary_score, _) = wrapper("ab", "ab_xx").unwrap();
+        let (_, _, non_boundary_score, _) = wrapper("ab", "xab_xx").unwrap();
+        assert!(boundary_score > non_boundary_score);
+
+        // No match at all.
+        assert_eq!(wrapper("z", "abc"), None);
+
+        // Empty query matches trivially with zero score and no indices.
+        assert_eq!(wrapper("", "abc"), Some((0, 0, 0, Vec::new())));
+    }
+
+    #[test]
+    fn test_span_rev() {
+        let wrapper = |needle: &str, haystack: &str| {
+            minspan::span_rev(
+                &needle.chars().collect::<Vec<char>>(),
+                &haystack.chars().collect::<Vec<char>>(),
+            )
+        };
+
+        // Same shortest-window semantics as `span`...
+        assert_eq!(wrapper("ab", "ab"), Some((0, 1)));
+        assert_eq!(wrapper("z", "acccccurlycurrelly"), None);
+
+        // ...but among equally short windows, prefers the rightmost one.
+        assert_eq!(wrapper("ab", "ababc"), Some((2, 3))); // `span` would pick (0, 1)
+        assert_eq!(wrapper("a", "aaa"), Some((2, 2)));
+
+        // A later, shorter window still beats an earlier, longer one.
+        assert_eq!(wrapper("ab", "axxxxxbab"), Some((7, 8)));
+
+        // Empty query matches a trivial zero-width span at the very end.
+        assert_eq!(wrapper("", "abc"), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_span_contiguous() {
+        let wrapper = |needle: &str, haystack: &str| {
+            minspan::span_contiguous(
+                &needle.chars().collect::<Vec<char>>(),
+                &haystack.chars().collect::<Vec<char>>(),
+            )
+        };
+
+        assert_eq!(wrapper("ab", "ab"), Some((0, 1)));
+        assert_eq!(wrapper("ab", "xabx"), Some((1, 2)));
+        assert_eq!(wrapper("ab", "aab"), Some((1, 2)));
+        assert_eq!(wrapper("", "abc"), Some((0, 0)));
+        assert_eq!(wrapper("abc", ""), None);
+        assert_eq!(wrapper("abc", "a"), None);
+
+        // Non-contiguous occurrences don't count, unlike `span`.
+        assert_eq!(wrapper("ace", "abcde"), None);
+
+        // Repeating needles exercise the critical-factorization period logic.
+        assert_eq!(wrapper("aaab", "aaaaab"), Some((2, 5)));
+        assert_eq!(wrapper("abab", "ababab"), Some((0, 3)));
+
+        // Must stay fast (no O(n*m) blowup) on an adversarial input: a long
+        // run that almost matches followed by the real match at the very end.
+        let large_haystack = "a".repeat(1_000_000) + "b";
+        assert_eq!(wrapper("ab", &large_haystack), Some((999_999, 1_000_000)));
+    }
+
+    #[test]
+    fn test_span_folded() {
+        let wrapper = |needle: &str, haystack: &str| {
+            minspan::span_folded(
+                &needle.chars().collect::<Vec<char>>(),
+                &haystack.chars().collect::<Vec<char>>(),
+            )
+        };
+
+        // Unlike `span`, case differences no longer cause a mismatch.
+        assert_eq!(wrapper("abc", "ABC"), Some((0, 2)));
+        assert_eq!(wrapper("abc", "aBc"), Some((0, 2)));
+
+        // A precomposed query character matches a decomposed sequence in
+        // `history` (e.g. "e" + combining acute accent matching "é"), and the
+        // returned span still refers to positions in the original `history`.
+        let decomposed_cafe: Vec<char> = "cafe\u{0301}".chars().collect(); // "café", decomposed
+        assert_eq!(decomposed_cafe.len(), 5);
+        let result = minspan::span_folded(&"café".chars().collect::<Vec<char>>(), &decomposed_cafe);
+        assert_eq!(result, Some((0, 4))); // Spans both chars of the combining sequence
+
+        // No match at all.
+        assert_eq!(wrapper("xyz", "abc"), None);
+
+        // Empty query matches trivially.
+        assert_eq!(wrapper("", "abc"), Some((0, 0)));
     }
 }